@@ -0,0 +1,60 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+/// Type alias for the result of tag/frame operations.
+pub type Result<T> = result::Result<T, Error>;
+
+/// Kinds of errors that may occur while performing metadata operations.
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// An error kind indicating that an IO error occurred.
+    Io(io::Error),
+    /// An error kind indicating that a tag was not found.
+    NoTag,
+    /// An error kind indicating that a string was not encoded with the specified encoding.
+    StringDecoding(Vec<u8>),
+    /// An error kind indicating that parsing a tag or frame failed.
+    Parsing,
+    /// An error kind indicating that a reader does not contain an ID3 tag of a supported version.
+    UnsupportedVersion(u8),
+    /// An error kind indicating that the requested operation is not supported by this crate.
+    UnsupportedFeature,
+    /// An error kind indicating that an invalid input parameter was provided.
+    InvalidInput,
+}
+
+/// A structure able to represent any error that may occur while performing metadata operations.
+#[derive(Debug)]
+pub struct Error {
+    /// The kind of error.
+    pub kind: ErrorKind,
+    /// A human readable string description of the error.
+    pub description: String,
+}
+
+impl Error {
+    /// Creates a new `Error` with the given kind and description.
+    pub fn new<D: Into<String>>(kind: ErrorKind, description: D) -> Error {
+        Error { kind, description: description.into() }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::new(ErrorKind::Io(err), "io error")
+    }
+}
@@ -0,0 +1,842 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+
+use ::stream::encoding::Encoding;
+use ::stream::frame as stream_frame;
+use ::tag::Version;
+use ::util;
+use ::{Error, ErrorKind};
+
+use super::Frame;
+
+/// The parsed content of a frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Content {
+    /// A value containing the parsed content of a text frame.
+    Text(String),
+    /// A value containing the parsed content of a web link frame.
+    Link(String),
+    /// A value containing the parsed content of an unsynchronized lyrics frame (USLT).
+    Lyrics(Lyrics),
+    /// A value containing the parsed content of a synchronised lyrics frame (SYLT).
+    SynchronisedLyrics(SynchronisedLyrics),
+    /// A value containing the parsed content of a comment frame (COMM).
+    Comment(Comment),
+    /// A value containing the parsed content of an extended text frame (TXXX).
+    ExtendedText(ExtendedText),
+    /// A value containing the parsed content of an extended link frame (WXXX).
+    ExtendedLink(ExtendedLink),
+    /// A value containing the parsed content of a picture frame (APIC).
+    Picture(Picture),
+    /// A value containing the parsed content of a general encapsulated object frame (GEOB).
+    EncapsulatedObject(EncapsulatedObject),
+    /// A value containing the parsed content of a popularimeter frame (POPM).
+    Popularimeter(Popularimeter),
+    /// A value containing the parsed content of a relative volume adjustment frame (RVA2).
+    RelativeVolumeAdjustment(RelativeVolumeAdjustment),
+    /// A value containing the parsed content of a chapter frame (CHAP).
+    Chapter(Chapter),
+    /// A value containing the parsed content of a table of contents frame (CTOC).
+    TableOfContents(TableOfContents),
+    /// A value containing the raw, unparsed content of an unknown or unhandled frame.
+    Unknown(Vec<u8>),
+}
+
+/// The parsed content of an unsynchronised lyrics (USLT) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Lyrics {
+    /// The three character ISO-639-2 language code.
+    pub lang: String,
+    /// A content descriptor.
+    pub description: String,
+    /// The lyrics.
+    pub text: String,
+}
+
+/// The parsed content of a comment (COMM) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Comment {
+    /// The three character ISO-639-2 language code.
+    pub lang: String,
+    /// A content descriptor.
+    pub description: String,
+    /// The comment text.
+    pub text: String,
+}
+
+/// The parsed content of an extended text (TXXX) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ExtendedText {
+    /// A content descriptor.
+    pub description: String,
+    /// The value of the frame.
+    pub value: String,
+}
+
+/// The parsed content of an extended link (WXXX) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ExtendedLink {
+    /// A content descriptor.
+    pub description: String,
+    /// The link.
+    pub link: String,
+}
+
+/// A picture type, as declared by the second byte of an APIC frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PictureType {
+    Other = 0,
+    Icon = 1,
+    OtherIcon = 2,
+    CoverFront = 3,
+    CoverBack = 4,
+    Leaflet = 5,
+    Media = 6,
+    LeadArtist = 7,
+    Artist = 8,
+    Conductor = 9,
+    Band = 10,
+    Composer = 11,
+    Lyricist = 12,
+    RecordingLocation = 13,
+    DuringRecording = 14,
+    DuringPerformance = 15,
+    ScreenCapture = 16,
+    BrightFish = 17,
+    Illustration = 18,
+    BandLogo = 19,
+    PublisherLogo = 20,
+}
+
+impl PictureType {
+    fn from_u8(n: u8) -> ::Result<PictureType> {
+        Ok(match n {
+            0 => PictureType::Other, 1 => PictureType::Icon, 2 => PictureType::OtherIcon,
+            3 => PictureType::CoverFront, 4 => PictureType::CoverBack, 5 => PictureType::Leaflet,
+            6 => PictureType::Media, 7 => PictureType::LeadArtist, 8 => PictureType::Artist,
+            9 => PictureType::Conductor, 10 => PictureType::Band, 11 => PictureType::Composer,
+            12 => PictureType::Lyricist, 13 => PictureType::RecordingLocation,
+            14 => PictureType::DuringRecording, 15 => PictureType::DuringPerformance,
+            16 => PictureType::ScreenCapture, 17 => PictureType::BrightFish,
+            18 => PictureType::Illustration, 19 => PictureType::BandLogo,
+            20 => PictureType::PublisherLogo,
+            _ => return Err(Error::new(ErrorKind::Parsing, "invalid picture type byte")),
+        })
+    }
+}
+
+/// The parsed content of a picture (APIC) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Picture {
+    /// The MIME type of the picture.
+    pub mime_type: String,
+    /// The picture type.
+    pub picture_type: PictureType,
+    /// A content descriptor.
+    pub description: String,
+    /// The raw image bytes.
+    pub data: Vec<u8>,
+}
+
+/// The parsed content of a general encapsulated object (GEOB) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EncapsulatedObject {
+    /// The encoding used for `filename` and `description`.
+    pub encoding: Encoding,
+    /// The MIME type of the encapsulated object.
+    pub mime_type: String,
+    /// The filename of the encapsulated object.
+    pub filename: String,
+    /// A content descriptor.
+    pub description: String,
+    /// The raw bytes of the encapsulated object.
+    pub data: Vec<u8>,
+}
+
+/// The parsed content of a popularimeter (POPM) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Popularimeter {
+    /// The email or user identifier the rating belongs to.
+    pub user: String,
+    /// The rating, from 0 (unrated) to 255.
+    pub rating: u8,
+    /// The play counter.
+    pub counter: u64,
+}
+
+/// A synchronised lyrics/text line and the timestamp (in the unit declared by
+/// `SynchronisedLyrics::timestamp_format`) at which it starts.
+pub type SynchronisedLyricsLine = (u32, String);
+
+/// The parsed content of a synchronised lyrics/text (SYLT) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SynchronisedLyrics {
+    /// The encoding used for `description` and the text of each line.
+    pub encoding: Encoding,
+    /// The three character ISO-639-2 language code.
+    pub lang: String,
+    /// The unit the timestamp of each line is given in: 1 = MPEG frames, 2 = milliseconds.
+    pub timestamp_format: u8,
+    /// The kind of content the lyrics/text represent: 0 = other, 1 = lyrics, 2 = text
+    /// transcription, etc.
+    pub content_type: u8,
+    /// A content descriptor.
+    pub description: String,
+    /// The synchronised lines, each paired with the timestamp at which it starts.
+    pub content: Vec<SynchronisedLyricsLine>,
+}
+
+/// A channel type, as declared by the first byte of each RVA2 channel block.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum ChannelType {
+    Other = 0,
+    MasterVolume = 1,
+    FrontRight = 2,
+    FrontLeft = 3,
+    BackRight = 4,
+    BackLeft = 5,
+    FrontCentre = 6,
+    BackCentre = 7,
+    Subwoofer = 8,
+}
+
+impl ChannelType {
+    fn from_u8(n: u8) -> ::Result<ChannelType> {
+        Ok(match n {
+            0 => ChannelType::Other, 1 => ChannelType::MasterVolume,
+            2 => ChannelType::FrontRight, 3 => ChannelType::FrontLeft,
+            4 => ChannelType::BackRight, 5 => ChannelType::BackLeft,
+            6 => ChannelType::FrontCentre, 7 => ChannelType::BackCentre,
+            8 => ChannelType::Subwoofer,
+            _ => return Err(Error::new(ErrorKind::Parsing, "invalid RVA2 channel type byte")),
+        })
+    }
+}
+
+/// The gain and peak volume for a single channel of a relative volume adjustment frame.
+#[derive(Copy, Clone, Debug)]
+pub struct VolumeAdjustment {
+    /// The volume adjustment, in decibels.
+    pub gain_db: f32,
+    /// The peak volume.
+    pub peak: u64,
+}
+
+impl PartialEq for VolumeAdjustment {
+    fn eq(&self, other: &VolumeAdjustment) -> bool {
+        self.gain_db.to_bits() == other.gain_db.to_bits() && self.peak == other.peak
+    }
+}
+
+impl Eq for VolumeAdjustment {}
+
+impl Hash for VolumeAdjustment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.gain_db.to_bits().hash(state);
+        self.peak.hash(state);
+    }
+}
+
+/// The parsed content of a relative volume adjustment (RVA2) frame.
+#[derive(Clone, Debug)]
+pub struct RelativeVolumeAdjustment {
+    /// An identifier used to link this adjustment to the audio it applies to.
+    pub identifier: String,
+    /// The gain/peak adjustment for each channel present in the frame.
+    pub adjustments: HashMap<ChannelType, VolumeAdjustment>,
+}
+
+impl PartialEq for RelativeVolumeAdjustment {
+    fn eq(&self, other: &RelativeVolumeAdjustment) -> bool {
+        self.identifier == other.identifier && self.adjustments == other.adjustments
+    }
+}
+
+impl Eq for RelativeVolumeAdjustment {}
+
+impl Hash for RelativeVolumeAdjustment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identifier.hash(state);
+        let mut channels: Vec<&ChannelType> = self.adjustments.keys().collect();
+        channels.sort();
+        for channel in channels {
+            channel.hash(state);
+            self.adjustments[channel].hash(state);
+        }
+    }
+}
+
+/// A value indicating "not set" for the byte-offset/time fields of a `Chapter`.
+pub const CHAPTER_VALUE_NOT_SET: u32 = 0xFFFFFFFF;
+
+/// The parsed content of a chapter (CHAP) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Chapter {
+    /// A unique identifier for this chapter, referenced by a `TableOfContents`.
+    pub element_id: String,
+    /// The start time of the chapter in milliseconds, or `CHAPTER_VALUE_NOT_SET`.
+    pub start_time: u32,
+    /// The end time of the chapter in milliseconds, or `CHAPTER_VALUE_NOT_SET`.
+    pub end_time: u32,
+    /// The start of the chapter as a byte offset into the file, or `CHAPTER_VALUE_NOT_SET`.
+    pub start_byte_offset: u32,
+    /// The end of the chapter as a byte offset into the file, or `CHAPTER_VALUE_NOT_SET`.
+    pub end_byte_offset: u32,
+    /// Sub-frames embedded in this chapter (e.g. a `TIT2` giving the chapter's title).
+    pub frames: Vec<Frame>,
+}
+
+/// The parsed content of a table of contents (CTOC) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TableOfContents {
+    /// A unique identifier for this table of contents.
+    pub element_id: String,
+    /// Whether this is the top-level table of contents (not referenced by any other CTOC).
+    pub top_level: bool,
+    /// Whether the child elements are ordered.
+    pub ordered: bool,
+    /// The element IDs of the child chapters/tables of contents, in order.
+    pub children: Vec<String>,
+    /// Sub-frames embedded in this table of contents (e.g. a `TIT2` giving its title).
+    pub frames: Vec<Frame>,
+}
+
+pub(crate) fn default_encoding(version: Version) -> Encoding {
+    if version == Version::Id3v24 { Encoding::UTF8 } else { Encoding::UTF16 }
+}
+
+fn be_u32(data: &[u8]) -> u32 {
+    ((data[0] as u32) << 24) | ((data[1] as u32) << 16) | ((data[2] as u32) << 8) | (data[3] as u32)
+}
+
+fn be_u32_bytes(n: u32) -> [u8; 4] {
+    [(n >> 24) as u8, (n >> 16) as u8, (n >> 8) as u8, n as u8]
+}
+
+/// Splits off a null-terminated ISO-8859-1 string from the front of `data`, returning the
+/// decoded string and the number of bytes consumed (including the terminator, if found).
+fn take_null_terminated_latin1(data: &[u8]) -> (String, usize) {
+    match data.iter().position(|&b| b == 0) {
+        Some(end) => (data[..end].iter().map(|&b| b as char).collect(), end + 1),
+        None => (data.iter().map(|&b| b as char).collect(), data.len()),
+    }
+}
+
+fn find_delim(encoding: Encoding, data: &[u8]) -> Option<usize> {
+    let dl = encoding.delim_len();
+    let mut i = 0;
+    while i + dl <= data.len() {
+        if data[i..i + dl].iter().all(|&b| b == 0) {
+            return Some(i);
+        }
+        i += dl;
+    }
+    None
+}
+
+fn decode_string(encoding: Encoding, data: &[u8]) -> ::Result<String> {
+    match encoding {
+        Encoding::Latin1 => Ok(data.iter().map(|&b| b as char).collect()),
+        Encoding::UTF8 => String::from_utf8(data.to_vec())
+            .map_err(|_| Error::new(ErrorKind::StringDecoding(data.to_vec()), "invalid utf-8 string")),
+        Encoding::UTF16 => decode_utf16_bom(data),
+        Encoding::UTF16BE => decode_utf16_be(data),
+    }
+}
+
+fn decode_utf16_bom(data: &[u8]) -> ::Result<String> {
+    if data.len() >= 2 && data[0] == 0xFF && data[1] == 0xFE {
+        decode_utf16_le(&data[2..])
+    } else if data.len() >= 2 && data[0] == 0xFE && data[1] == 0xFF {
+        decode_utf16_be(&data[2..])
+    } else {
+        decode_utf16_le(data)
+    }
+}
+
+fn decode_utf16_le(data: &[u8]) -> ::Result<String> {
+    let units: Vec<u16> = data.chunks(2).filter(|c| c.len() == 2)
+        .map(|c| (c[0] as u16) | ((c[1] as u16) << 8)).collect();
+    String::from_utf16(&units).map_err(|_| Error::new(ErrorKind::Parsing, "invalid utf-16 string"))
+}
+
+fn decode_utf16_be(data: &[u8]) -> ::Result<String> {
+    let units: Vec<u16> = data.chunks(2).filter(|c| c.len() == 2)
+        .map(|c| ((c[0] as u16) << 8) | (c[1] as u16)).collect();
+    String::from_utf16(&units).map_err(|_| Error::new(ErrorKind::Parsing, "invalid utf-16 string"))
+}
+
+/// Splits off an encoding-delimited string from the front of `data`, returning the decoded
+/// string and the number of bytes consumed (including the terminator, if found).
+fn take_delimited_string(encoding: Encoding, data: &[u8]) -> ::Result<(String, usize)> {
+    let dl = encoding.delim_len();
+    match find_delim(encoding, data) {
+        Some(pos) => Ok((decode_string(encoding, &data[..pos])?, pos + dl)),
+        None => Ok((decode_string(encoding, data)?, data.len())),
+    }
+}
+
+/// Decodes the remainder of `data` (the last field of a frame) as a string, stripping a
+/// trailing terminator if one is present.
+fn decode_final_string(encoding: Encoding, data: &[u8]) -> ::Result<String> {
+    let dl = encoding.delim_len();
+    let body = if data.len() >= dl && data[data.len() - dl..].iter().all(|&b| b == 0) {
+        &data[..data.len() - dl]
+    } else {
+        data
+    };
+    decode_string(encoding, body)
+}
+
+fn encode_delimited_string(encoding: Encoding, text: &str) -> Vec<u8> {
+    let mut out = encode_final_string(encoding, text);
+    out.extend(vec![0u8; encoding.delim_len()]);
+    out
+}
+
+fn encode_final_string(encoding: Encoding, text: &str) -> Vec<u8> {
+    match encoding {
+        Encoding::Latin1 => text.chars().map(|c| c as u8).collect(),
+        Encoding::UTF8 => text.as_bytes().to_vec(),
+        Encoding::UTF16 => {
+            let mut out = vec![0xFF, 0xFE];
+            for unit in text.encode_utf16() {
+                out.extend(&unit.to_le_bytes());
+            }
+            out
+        },
+        Encoding::UTF16BE => {
+            let mut out = Vec::new();
+            for unit in text.encode_utf16() {
+                out.extend(&unit.to_be_bytes());
+            }
+            out
+        },
+    }
+}
+
+fn decode_text(data: &[u8]) -> ::Result<Content> {
+    if data.is_empty() {
+        return Err(Error::new(ErrorKind::Parsing, "text frame is empty"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    Ok(Content::Text(decode_final_string(encoding, &data[1..])?))
+}
+
+fn encode_text(text: &str, version: Version) -> Vec<u8> {
+    let encoding = default_encoding(version);
+    let mut data = vec![encoding as u8];
+    if encoding == Encoding::UTF8 || encoding == Encoding::Latin1 {
+        data.extend(encode_final_string(encoding, text));
+    } else {
+        data.extend(util::string_to_utf16(text));
+    }
+    data
+}
+
+fn decode_link(data: &[u8]) -> ::Result<Content> {
+    Ok(Content::Link(decode_string(Encoding::Latin1, data)?))
+}
+
+fn encode_link(link: &str) -> Vec<u8> {
+    encode_final_string(Encoding::Latin1, link)
+}
+
+fn decode_lang_described_text(data: &[u8]) -> ::Result<(String, String, String)> {
+    if data.len() < 4 {
+        return Err(Error::new(ErrorKind::Parsing, "frame is too short to contain a language"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    let lang = decode_string(Encoding::Latin1, &data[1..4])?;
+    let (description, consumed) = take_delimited_string(encoding, &data[4..])?;
+    let text = decode_final_string(encoding, &data[4 + consumed..])?;
+    Ok((lang, description, text))
+}
+
+fn encode_lang_described_text(lang: &str, description: &str, text: &str, version: Version) -> Vec<u8> {
+    let encoding = default_encoding(version);
+    let mut data = vec![encoding as u8];
+    let lang_bytes: Vec<u8> = lang.bytes().take(3).collect();
+    data.extend(&lang_bytes);
+    data.extend(vec![0u8; 3 - lang_bytes.len()]);
+    data.extend(encode_delimited_string(encoding, description));
+    data.extend(encode_final_string(encoding, text));
+    data
+}
+
+fn decode_comment(data: &[u8]) -> ::Result<Content> {
+    let (lang, description, text) = decode_lang_described_text(data)?;
+    Ok(Content::Comment(Comment { lang, description, text }))
+}
+
+fn encode_comment(comment: &Comment, version: Version) -> Vec<u8> {
+    encode_lang_described_text(&comment.lang, &comment.description, &comment.text, version)
+}
+
+fn decode_lyrics(data: &[u8]) -> ::Result<Content> {
+    let (lang, description, text) = decode_lang_described_text(data)?;
+    Ok(Content::Lyrics(Lyrics { lang, description, text }))
+}
+
+fn encode_lyrics(lyrics: &Lyrics, version: Version) -> Vec<u8> {
+    encode_lang_described_text(&lyrics.lang, &lyrics.description, &lyrics.text, version)
+}
+
+fn decode_extended_text(data: &[u8]) -> ::Result<Content> {
+    if data.is_empty() {
+        return Err(Error::new(ErrorKind::Parsing, "TXXX frame is empty"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    let (description, consumed) = take_delimited_string(encoding, &data[1..])?;
+    let value = decode_final_string(encoding, &data[1 + consumed..])?;
+    Ok(Content::ExtendedText(ExtendedText { description, value }))
+}
+
+fn encode_extended_text(extended_text: &ExtendedText, version: Version) -> Vec<u8> {
+    let encoding = default_encoding(version);
+    let mut data = vec![encoding as u8];
+    data.extend(encode_delimited_string(encoding, &extended_text.description));
+    data.extend(encode_final_string(encoding, &extended_text.value));
+    data
+}
+
+fn decode_extended_link(data: &[u8]) -> ::Result<Content> {
+    if data.is_empty() {
+        return Err(Error::new(ErrorKind::Parsing, "WXXX frame is empty"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    let (description, consumed) = take_delimited_string(encoding, &data[1..])?;
+    let link = decode_string(Encoding::Latin1, &data[1 + consumed..])?;
+    Ok(Content::ExtendedLink(ExtendedLink { description, link }))
+}
+
+fn encode_extended_link(extended_link: &ExtendedLink, version: Version) -> Vec<u8> {
+    let encoding = default_encoding(version);
+    let mut data = vec![encoding as u8];
+    data.extend(encode_delimited_string(encoding, &extended_link.description));
+    data.extend(encode_final_string(Encoding::Latin1, &extended_link.link));
+    data
+}
+
+fn decode_picture(data: &[u8]) -> ::Result<Content> {
+    if data.is_empty() {
+        return Err(Error::new(ErrorKind::Parsing, "APIC frame is empty"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    let (mime_type, consumed) = take_null_terminated_latin1(&data[1..]);
+    let mut pos = 1 + consumed;
+    if pos >= data.len() {
+        return Err(Error::new(ErrorKind::Parsing, "APIC frame missing picture type"));
+    }
+    let picture_type = PictureType::from_u8(data[pos])?;
+    pos += 1;
+    let (description, consumed) = take_delimited_string(encoding, &data[pos..])?;
+    pos += consumed;
+    Ok(Content::Picture(Picture {
+        mime_type,
+        picture_type,
+        description,
+        data: data[pos..].to_vec(),
+    }))
+}
+
+fn encode_picture(picture: &Picture, version: Version) -> Vec<u8> {
+    let encoding = default_encoding(version);
+    let mut data = vec![encoding as u8];
+    data.extend(picture.mime_type.as_bytes());
+    data.push(0);
+    data.push(picture.picture_type as u8);
+    data.extend(encode_delimited_string(encoding, &picture.description));
+    data.extend(&picture.data);
+    data
+}
+
+fn decode_encapsulated_object(data: &[u8]) -> ::Result<Content> {
+    if data.is_empty() {
+        return Err(Error::new(ErrorKind::Parsing, "GEOB frame is empty"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    let (mime_type, consumed) = take_null_terminated_latin1(&data[1..]);
+    let mut pos = 1 + consumed;
+    let (filename, consumed) = take_delimited_string(encoding, &data[pos..])?;
+    pos += consumed;
+    let (description, consumed) = take_delimited_string(encoding, &data[pos..])?;
+    pos += consumed;
+    Ok(Content::EncapsulatedObject(EncapsulatedObject {
+        encoding,
+        mime_type,
+        filename,
+        description,
+        data: data[pos..].to_vec(),
+    }))
+}
+
+fn encode_encapsulated_object(geob: &EncapsulatedObject) -> Vec<u8> {
+    let mut data = vec![geob.encoding as u8];
+    data.extend(geob.mime_type.as_bytes());
+    data.push(0);
+    data.extend(encode_delimited_string(geob.encoding, &geob.filename));
+    data.extend(encode_delimited_string(geob.encoding, &geob.description));
+    data.extend(&geob.data);
+    data
+}
+
+fn decode_popularimeter(data: &[u8]) -> ::Result<Content> {
+    let (user, consumed) = take_null_terminated_latin1(data);
+    if consumed >= data.len() {
+        return Err(Error::new(ErrorKind::Parsing, "POPM frame missing rating byte"));
+    }
+    let rating = data[consumed];
+    let mut counter: u64 = 0;
+    for &byte in &data[consumed + 1..] {
+        counter = (counter << 8) | (byte as u64);
+    }
+    Ok(Content::Popularimeter(Popularimeter { user, rating, counter }))
+}
+
+fn encode_popularimeter(popm: &Popularimeter) -> Vec<u8> {
+    let mut data = popm.user.as_bytes().to_vec();
+    data.push(0);
+    data.push(popm.rating);
+    let mut counter_bytes = Vec::new();
+    let mut n = popm.counter;
+    loop {
+        counter_bytes.push((n & 0xFF) as u8);
+        n >>= 8;
+        if n == 0 {
+            break;
+        }
+    }
+    while counter_bytes.len() < 4 {
+        counter_bytes.push(0);
+    }
+    counter_bytes.reverse();
+    data.extend(counter_bytes);
+    data
+}
+
+fn decode_synchronised_lyrics(data: &[u8]) -> ::Result<Content> {
+    if data.len() < 6 {
+        return Err(Error::new(ErrorKind::Parsing, "SYLT frame is too short"));
+    }
+    let encoding = Encoding::from_u8(data[0])?;
+    let lang = decode_string(Encoding::Latin1, &data[1..4])?;
+    let timestamp_format = data[4];
+    let content_type = data[5];
+    let (description, consumed) = take_delimited_string(encoding, &data[6..])?;
+    let mut pos = 6 + consumed;
+    let mut content = Vec::new();
+    while pos < data.len() {
+        let (text, consumed) = take_delimited_string(encoding, &data[pos..])?;
+        pos += consumed;
+        if pos + 4 > data.len() {
+            return Err(Error::new(ErrorKind::Parsing, "SYLT entry is missing its timestamp"));
+        }
+        content.push((be_u32(&data[pos..pos + 4]), text));
+        pos += 4;
+    }
+    Ok(Content::SynchronisedLyrics(SynchronisedLyrics {
+        encoding, lang, timestamp_format, content_type, description, content,
+    }))
+}
+
+fn encode_synchronised_lyrics(lyrics: &SynchronisedLyrics) -> Vec<u8> {
+    let mut data = vec![lyrics.encoding as u8];
+    let lang_bytes: Vec<u8> = lyrics.lang.bytes().take(3).collect();
+    data.extend(&lang_bytes);
+    data.extend(vec![0u8; 3 - lang_bytes.len()]);
+    data.push(lyrics.timestamp_format);
+    data.push(lyrics.content_type);
+    data.extend(encode_delimited_string(lyrics.encoding, &lyrics.description));
+    for &(timestamp, ref text) in &lyrics.content {
+        data.extend(encode_delimited_string(lyrics.encoding, text));
+        data.extend(&be_u32_bytes(timestamp));
+    }
+    data
+}
+
+fn decode_relative_volume_adjustment(data: &[u8]) -> ::Result<Content> {
+    let (identifier, mut pos) = take_null_terminated_latin1(data);
+    let mut adjustments = HashMap::new();
+    while pos < data.len() {
+        if pos + 4 > data.len() {
+            return Err(Error::new(ErrorKind::Parsing, "RVA2 channel block is truncated"));
+        }
+        let channel_type = ChannelType::from_u8(data[pos])?;
+        pos += 1;
+        let raw = (((data[pos] as u16) << 8) | (data[pos + 1] as u16)) as i16;
+        pos += 2;
+        let gain_db = raw as f32 / 512.0;
+        let bits = data[pos];
+        pos += 1;
+        let peak_bytes = (bits as usize).div_ceil(8);
+        if pos + peak_bytes > data.len() {
+            return Err(Error::new(ErrorKind::Parsing, "RVA2 peak volume is truncated"));
+        }
+        let mut peak: u64 = 0;
+        for &byte in &data[pos..pos + peak_bytes] {
+            peak = (peak << 8) | (byte as u64);
+        }
+        pos += peak_bytes;
+        adjustments.insert(channel_type, VolumeAdjustment { gain_db, peak });
+    }
+    Ok(Content::RelativeVolumeAdjustment(RelativeVolumeAdjustment { identifier, adjustments }))
+}
+
+fn encode_relative_volume_adjustment(rva: &RelativeVolumeAdjustment) -> Vec<u8> {
+    let mut data = rva.identifier.as_bytes().to_vec();
+    data.push(0);
+    let mut channels: Vec<&ChannelType> = rva.adjustments.keys().collect();
+    channels.sort();
+    for channel in channels {
+        let adjustment = &rva.adjustments[channel];
+        data.push(*channel as u8);
+        let raw = (adjustment.gain_db * 512.0).round()
+            .max(i16::MIN as f32).min(i16::MAX as f32) as i16;
+        let raw_bytes = (raw as u16).to_be_bytes();
+        data.extend(&raw_bytes);
+        let mut peak_bytes = Vec::new();
+        let mut n = adjustment.peak;
+        loop {
+            peak_bytes.push((n & 0xFF) as u8);
+            n >>= 8;
+            if n == 0 {
+                break;
+            }
+        }
+        peak_bytes.reverse();
+        data.push((peak_bytes.len() * 8) as u8);
+        data.extend(peak_bytes);
+    }
+    data
+}
+
+pub(crate) fn decode_chapter(data: &[u8], version: Version, unsynchronization: bool, depth: u8) -> ::Result<Content> {
+    let (element_id, mut pos) = take_null_terminated_latin1(data);
+    if data.len() < pos + 16 {
+        return Err(Error::new(ErrorKind::Parsing, "CHAP frame is too short"));
+    }
+    let start_time = be_u32(&data[pos..pos + 4]);
+    pos += 4;
+    let end_time = be_u32(&data[pos..pos + 4]);
+    pos += 4;
+    let start_byte_offset = be_u32(&data[pos..pos + 4]);
+    pos += 4;
+    let end_byte_offset = be_u32(&data[pos..pos + 4]);
+    pos += 4;
+    let frames = stream_frame::decode_embedded_frames(&data[pos..], version, unsynchronization, depth)?;
+    Ok(Content::Chapter(Chapter {
+        element_id, start_time, end_time, start_byte_offset, end_byte_offset, frames,
+    }))
+}
+
+pub(crate) fn encode_chapter(chapter: &Chapter, version: Version, unsynchronization: bool, depth: u8) -> ::Result<Vec<u8>> {
+    if depth >= stream_frame::MAX_CHAPTER_RECURSION_DEPTH {
+        return Err(Error::new(ErrorKind::InvalidInput, "chapter frame nesting exceeds the maximum recursion depth"));
+    }
+    let mut data = chapter.element_id.as_bytes().to_vec();
+    data.push(0);
+    data.extend(&be_u32_bytes(chapter.start_time));
+    data.extend(&be_u32_bytes(chapter.end_time));
+    data.extend(&be_u32_bytes(chapter.start_byte_offset));
+    data.extend(&be_u32_bytes(chapter.end_byte_offset));
+    for sub_frame in &chapter.frames {
+        let mut buf = Vec::new();
+        sub_frame.write_to_with_depth(&mut buf, version, unsynchronization, depth + 1)?;
+        data.extend(buf);
+    }
+    Ok(data)
+}
+
+pub(crate) fn decode_table_of_contents(data: &[u8], version: Version, unsynchronization: bool, depth: u8) -> ::Result<Content> {
+    let (element_id, mut pos) = take_null_terminated_latin1(data);
+    if pos + 2 > data.len() {
+        return Err(Error::new(ErrorKind::Parsing, "CTOC frame is too short"));
+    }
+    let flags_byte = data[pos];
+    pos += 1;
+    let top_level = flags_byte & 0x01 != 0;
+    let ordered = flags_byte & 0x02 != 0;
+    let entry_count = data[pos] as usize;
+    pos += 1;
+    let mut children = Vec::with_capacity(entry_count);
+    for _ in 0..entry_count {
+        let (child, consumed) = take_null_terminated_latin1(&data[pos..]);
+        children.push(child);
+        pos += consumed;
+    }
+    let frames = stream_frame::decode_embedded_frames(&data[pos..], version, unsynchronization, depth)?;
+    Ok(Content::TableOfContents(TableOfContents { element_id, top_level, ordered, children, frames }))
+}
+
+pub(crate) fn encode_table_of_contents(toc: &TableOfContents, version: Version, unsynchronization: bool, depth: u8) -> ::Result<Vec<u8>> {
+    if depth >= stream_frame::MAX_CHAPTER_RECURSION_DEPTH {
+        return Err(Error::new(ErrorKind::InvalidInput, "table of contents frame nesting exceeds the maximum recursion depth"));
+    }
+    let mut data = toc.element_id.as_bytes().to_vec();
+    data.push(0);
+    let mut flags_byte = 0u8;
+    if toc.top_level {
+        flags_byte |= 0x01;
+    }
+    if toc.ordered {
+        flags_byte |= 0x02;
+    }
+    data.push(flags_byte);
+    data.push(toc.children.len() as u8);
+    for child in &toc.children {
+        data.extend(child.as_bytes());
+        data.push(0);
+    }
+    for sub_frame in &toc.frames {
+        let mut buf = Vec::new();
+        sub_frame.write_to_with_depth(&mut buf, version, unsynchronization, depth + 1)?;
+        data.extend(buf);
+    }
+    Ok(data)
+}
+
+/// Decodes the content of a frame with the given ID from its raw, already extracted body.
+///
+/// `CHAP`/`CTOC` are not handled here because decoding their embedded sub-frames requires the
+/// tag version and unsynchronization state; see `stream::frame::decode_body`.
+pub fn decode<R: Read>(mut reader: R, id: &str, _flags: super::Flags) -> ::Result<Content> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    match id {
+        "COMM" => decode_comment(&data),
+        "USLT" => decode_lyrics(&data),
+        "SYLT" => decode_synchronised_lyrics(&data),
+        "APIC" => decode_picture(&data),
+        "GEOB" => decode_encapsulated_object(&data),
+        "POPM" => decode_popularimeter(&data),
+        "RVA2" => decode_relative_volume_adjustment(&data),
+        "TXXX" => decode_extended_text(&data),
+        "WXXX" => decode_extended_link(&data),
+        _ if id.starts_with('T') => decode_text(&data),
+        _ if id.starts_with('W') => decode_link(&data),
+        _ => Ok(Content::Unknown(data)),
+    }
+}
+
+/// Encodes the content of a frame for the given version, returning its raw body bytes (without
+/// the frame header, flags or group/encryption bytes).
+pub fn encode(content: &Content, version: Version, unsynchronization: bool, depth: u8) -> ::Result<Vec<u8>> {
+    Ok(match *content {
+        Content::Text(ref text) => encode_text(text, version),
+        Content::Link(ref link) => encode_link(link),
+        Content::Lyrics(ref lyrics) => encode_lyrics(lyrics, version),
+        Content::SynchronisedLyrics(ref lyrics) => encode_synchronised_lyrics(lyrics),
+        Content::Comment(ref comment) => encode_comment(comment, version),
+        Content::ExtendedText(ref extended_text) => encode_extended_text(extended_text, version),
+        Content::ExtendedLink(ref extended_link) => encode_extended_link(extended_link, version),
+        Content::Picture(ref picture) => encode_picture(picture, version),
+        Content::EncapsulatedObject(ref geob) => encode_encapsulated_object(geob),
+        Content::Popularimeter(ref popm) => encode_popularimeter(popm),
+        Content::RelativeVolumeAdjustment(ref rva) => encode_relative_volume_adjustment(rva),
+        Content::Chapter(ref chapter) => encode_chapter(chapter, version, unsynchronization, depth)?,
+        Content::TableOfContents(ref toc) => encode_table_of_contents(toc, version, unsynchronization, depth)?,
+        Content::Unknown(ref data) => data.clone(),
+    })
+}
@@ -0,0 +1,99 @@
+/// The flags that can be applied to a frame, as declared by the two flag bytes that follow a
+/// ID3v2.3/ID3v2.4 frame header (ID3v2.2 frames have no flags at all).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Flags {
+    /// Indicates whether or not this frame should be discarded if the tag is altered.
+    pub tag_alter_preservation: bool,
+    /// Indicates whether or not this frame should be discarded if the file is altered.
+    pub file_alter_preservation: bool,
+    /// Indicates whether or not this frame is read only.
+    pub read_only: bool,
+    /// Indicates whether or not the frame is compressed using zlib.
+    pub compression: bool,
+    /// Indicates whether or not the frame is encrypted.
+    pub encryption: bool,
+    /// Indicates whether or not the frame belongs in a group with other frames.
+    pub grouping_identity: bool,
+    /// Indicates whether or not unsynchronisation was applied to this frame.
+    pub unsynchronization: bool,
+    /// Indicates whether or not a data length indicator is present.
+    pub data_length_indicator: bool,
+}
+
+impl Default for Flags {
+    fn default() -> Flags {
+        Flags::new()
+    }
+}
+
+impl Flags {
+    /// Creates a new `Flags` with every flag cleared.
+    pub fn new() -> Flags {
+        Flags {
+            tag_alter_preservation: false,
+            file_alter_preservation: false,
+            read_only: false,
+            compression: false,
+            encryption: false,
+            grouping_identity: false,
+            unsynchronization: false,
+            data_length_indicator: false,
+        }
+    }
+
+    /// Parses the two raw flag bytes of a frame header for the given major version (3 or 4).
+    /// Version 2 frames have no flag bytes, so this always returns cleared flags for version 2.
+    pub fn from_bytes(bytes: [u8; 2], version: u8) -> Flags {
+        let mut flags = Flags::new();
+        match version {
+            3 => {
+                flags.tag_alter_preservation = bytes[0] & 0x80 != 0;
+                flags.file_alter_preservation = bytes[0] & 0x40 != 0;
+                flags.read_only = bytes[0] & 0x20 != 0;
+                flags.compression = bytes[1] & 0x80 != 0;
+                flags.encryption = bytes[1] & 0x40 != 0;
+                flags.grouping_identity = bytes[1] & 0x20 != 0;
+            },
+            4 => {
+                flags.tag_alter_preservation = bytes[0] & 0x40 != 0;
+                flags.file_alter_preservation = bytes[0] & 0x20 != 0;
+                flags.read_only = bytes[0] & 0x10 != 0;
+                flags.grouping_identity = bytes[1] & 0x40 != 0;
+                flags.compression = bytes[1] & 0x08 != 0;
+                flags.encryption = bytes[1] & 0x04 != 0;
+                flags.unsynchronization = bytes[1] & 0x02 != 0;
+                flags.data_length_indicator = bytes[1] & 0x01 != 0;
+            },
+            _ => {},
+        }
+        flags
+    }
+
+    /// Converts these flags to their two-byte wire representation for the given major version
+    /// (2, 3 or 4). Version 2 has no frame flags and always encodes as zero bytes.
+    pub fn to_bytes(&self, version: u8) -> Vec<u8> {
+        let mut bytes = vec![0u8, 0u8];
+        match version {
+            3 => {
+                if self.tag_alter_preservation { bytes[0] |= 0x80; }
+                if self.file_alter_preservation { bytes[0] |= 0x40; }
+                if self.read_only { bytes[0] |= 0x20; }
+                if self.compression { bytes[1] |= 0x80; }
+                if self.encryption { bytes[1] |= 0x40; }
+                if self.grouping_identity { bytes[1] |= 0x20; }
+            },
+            4 => {
+                if self.tag_alter_preservation { bytes[0] |= 0x40; }
+                if self.file_alter_preservation { bytes[0] |= 0x20; }
+                if self.read_only { bytes[0] |= 0x10; }
+                if self.grouping_identity { bytes[1] |= 0x40; }
+                if self.compression { bytes[1] |= 0x08; }
+                if self.encryption { bytes[1] |= 0x04; }
+                if self.unsynchronization { bytes[1] |= 0x02; }
+                if self.data_length_indicator { bytes[1] |= 0x01; }
+            },
+            _ => {},
+        }
+        bytes
+    }
+}
@@ -4,7 +4,13 @@ use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
 use std::str;
 
-pub use self::content::{Content, ExtendedText, ExtendedLink, Comment, Lyrics, Picture, PictureType};
+pub use self::content::{
+    Content, ExtendedText, ExtendedLink, Comment, Lyrics, Picture, PictureType,
+    SynchronisedLyrics, SynchronisedLyricsLine, Popularimeter, RelativeVolumeAdjustment,
+    ChannelType, VolumeAdjustment, EncapsulatedObject, Chapter, TableOfContents,
+    CHAPTER_VALUE_NOT_SET,
+};
+pub use self::restrictions::{Restrictions, TextEncodingRestriction, TextFieldSizeRestriction, ImageEncodingRestriction, ImageSizeRestriction};
 pub use self::timestamp::Timestamp;
 
 use self::flags::Flags;
@@ -12,9 +18,10 @@ use ::stream::frame::{self, v2, v3, v4};
 
 use ::tag::{self, Version};
 
-mod content;
+pub(crate) mod content;
 #[doc(hidden)]
 pub mod flags;
+mod restrictions;
 mod timestamp;
 
 
@@ -33,6 +40,18 @@ pub struct Frame {
     /// The frame flags.
     #[doc(hidden)]
     pub flags: Flags,
+    /// The group symbol byte, present on the wire when `flags.grouping_identity` is set.
+    #[doc(hidden)]
+    pub group_identifier: Option<u8>,
+    /// The encryption method byte, present on the wire when `flags.encryption` is set.
+    #[doc(hidden)]
+    pub encryption_method: Option<u8>,
+    /// The data length indicator: the frame's size before compression/encryption/
+    /// unsynchronization were applied. Present on the wire when `flags.compression` is set (for
+    /// ID3v2.3, as the "decompressed size") or when `flags.data_length_indicator` is set (for
+    /// ID3v2.4).
+    #[doc(hidden)]
+    pub data_length: Option<u32>,
 }
 
 impl PartialEq for Frame {
@@ -77,7 +96,7 @@ impl Frame {
     /// If the id's length is not 3 or 4 bytes long or not known.
     pub fn with_content(id: &str, content: Content) -> Frame {
         assert!({
-            let l = id.bytes().count();
+            let l = id.len();
             l == 3 || l == 4
         });
         Frame {
@@ -96,8 +115,11 @@ impl Frame {
                     b.next().unwrap(),
                 ]
             },
-            content: content,
+            content,
             flags: Flags::new(),
+            group_identifier: None,
+            encryption_method: None,
+            data_length: None,
         }
     }
 
@@ -152,6 +174,61 @@ impl Frame {
         self.flags.file_alter_preservation = file_alter_preservation;
     }
 
+    /// Returns the group symbol byte, if the grouping_identity flag is set.
+    pub fn group_identifier(&self) -> Option<u8> {
+        self.group_identifier
+    }
+
+    /// Sets the group symbol byte and the grouping_identity flag.
+    pub fn set_group_identifier(&mut self, group_identifier: u8) {
+        self.flags.grouping_identity = true;
+        self.group_identifier = Some(group_identifier);
+    }
+
+    /// Clears the group symbol byte and the grouping_identity flag.
+    pub fn remove_group_identifier(&mut self) {
+        self.flags.grouping_identity = false;
+        self.group_identifier = None;
+    }
+
+    /// Returns the encryption method byte, if the encryption flag is set.
+    pub fn encryption_method(&self) -> Option<u8> {
+        self.encryption_method
+    }
+
+    /// Sets the encryption method byte and the encryption flag.
+    pub fn set_encryption_method(&mut self, encryption_method: u8) {
+        self.flags.encryption = true;
+        self.encryption_method = Some(encryption_method);
+    }
+
+    /// Returns the frame's data length (its size before compression/encryption/
+    /// unsynchronization), if the compression flag (ID3v2.3) or data_length_indicator flag
+    /// (ID3v2.4) is set.
+    pub fn data_length(&self) -> Option<u32> {
+        self.data_length
+    }
+
+    /// Sets the data length and the data_length_indicator flag, so that it is written out as an
+    /// ID3v2.4 data length indicator. Also sets `data_length` directly when `set_compression` is
+    /// used to write the frame as an ID3v2.3 "decompressed size".
+    pub fn set_data_length(&mut self, data_length: u32) {
+        self.flags.data_length_indicator = true;
+        self.data_length = Some(data_length);
+    }
+
+    /// Clears the data length and the data_length_indicator flag.
+    pub fn remove_data_length(&mut self) {
+        self.flags.data_length_indicator = false;
+        self.data_length = None;
+    }
+
+    /// Clears the encryption method byte and the encryption flag.
+    pub fn remove_encryption_method(&mut self) {
+        self.flags.encryption = false;
+        self.encryption_method = None;
+    }
+
     /// Attempts to read a frame from the reader.
     ///
     /// Returns a tuple containing the number of bytes read and a frame. If pading is encountered
@@ -170,11 +247,18 @@ impl Frame {
     ///
     /// Only writing to versions 2, 3, and 4 is supported. Attempting to write using any other
     /// version will return an error with kind `UnsupportedVersion`.
-    pub fn write_to(&self, writer: &mut Write, version: tag::Version, unsynchronization: bool) -> ::Result<u32> {
+    pub fn write_to(&self, writer: &mut dyn Write, version: tag::Version, unsynchronization: bool) -> ::Result<u32> {
+        self.write_to_with_depth(writer, version, unsynchronization, 0)
+    }
+
+    /// Same as `write_to`, but tracks the `CHAP`/`CTOC` nesting depth so that a deeply-nested
+    /// embedded frame tree is rejected instead of recursing without bound, mirroring the guard
+    /// `stream::frame::decode_embedded_frames` applies on the read side.
+    pub(crate) fn write_to_with_depth(&self, writer: &mut dyn Write, version: tag::Version, unsynchronization: bool, depth: u8) -> ::Result<u32> {
         match version {
-            tag::Id3v22 => v2::write(writer, self, unsynchronization),
-            tag::Id3v23 => v3::write(writer, self, unsynchronization),
-            tag::Id3v24 => v4::write(writer, self),
+            tag::Id3v22 => v2::write(writer, self, unsynchronization, depth),
+            tag::Id3v23 => v3::write(writer, self, unsynchronization, depth),
+            tag::Id3v24 => v4::write(writer, self, depth),
         }
     }
 
@@ -196,7 +280,7 @@ impl Frame {
     /// assert_eq!(&txxx_frame.text().unwrap()[..], "description: value");
     /// ```
     #[deprecated(note = "Format using fmt::Display")]
-    pub fn text(&self) -> Option<Cow<str>> {
+    pub fn text(&self) -> Option<Cow<'_, str>> {
         Some(Cow::Owned(format!("{}", self)))
     }
 }
@@ -207,10 +291,16 @@ impl fmt::Display for Frame {
             Content::Text(ref content) => write!(f, "{}", content),
             Content::Link(ref content) => write!(f, "{}", content),
             Content::Lyrics(ref content) => write!(f, "{}", content.text),
+            Content::SynchronisedLyrics(ref content) => write!(f, "{}: {} synced line(s)", content.description, content.content.len()),
             Content::ExtendedText(ref content) => write!(f, "{}: {}", content.description, content.value),
             Content::ExtendedLink(ref content) => write!(f, "{}: {}", content.description, content.link),
             Content::Comment(ref content) => write!(f, "{}: {}", content.description, content.text),
+            Content::Popularimeter(ref content) => write!(f, "{}: {}/255", content.user, content.rating),
+            Content::RelativeVolumeAdjustment(ref content) => write!(f, "{}: {} channel(s)", content.identifier, content.adjustments.len()),
             Content::Picture(ref content) => write!(f, "{}: {:?} ({:?})", content.description, content.picture_type, content.mime_type),
+            Content::EncapsulatedObject(ref content) => write!(f, "{}: {} ({} bytes)", content.filename, content.mime_type, content.data.len()),
+            Content::Chapter(ref content) => write!(f, "{} ({}ms-{}ms)", content.element_id, content.start_time, content.end_time),
+            Content::TableOfContents(ref content) => write!(f, "{} ({} children)", content.element_id, content.children.len()),
             Content::Unknown(ref content) => write!(f, "unknown, {} bytes", content.len()),
         }
     }
@@ -219,6 +309,8 @@ impl fmt::Display for Frame {
 // Tests {{{
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
     use frame::{Frame, Flags};
     use ::stream::encoding::Encoding;
@@ -279,14 +371,14 @@ mod tests {
 
         let mut data = Vec::new();
         data.push(encoding as u8);
-        data.extend(::util::string_to_utf16(text).into_iter());
+        data.extend(::util::string_to_utf16(text));
 
         parse_data(&mut frame, &data[..]).unwrap();
 
         let mut bytes = Vec::new();
         bytes.extend(id.bytes());
-        bytes.extend((&u32_to_bytes(data.len() as u32)[1..]).iter().cloned());
-        bytes.extend(data.into_iter());
+        bytes.extend(u32_to_bytes(data.len() as u32)[1..].iter().cloned());
+        bytes.extend(data);
 
         let mut writer = Vec::new();
         frame.write_to(&mut writer, tag::Id3v22, false).unwrap();
@@ -303,15 +395,15 @@ mod tests {
 
         let mut data = Vec::new();
         data.push(encoding as u8);
-        data.extend(::util::string_to_utf16(text).into_iter());
+        data.extend(::util::string_to_utf16(text));
 
         parse_data(&mut frame, &data[..]).unwrap();
 
         let mut bytes = Vec::new();
         bytes.extend(id.bytes());
-        bytes.extend(u32_to_bytes(data.len() as u32).into_iter());
+        bytes.extend(u32_to_bytes(data.len() as u32));
         bytes.extend([0x00, 0x00].iter().cloned());
-        bytes.extend(data.into_iter());
+        bytes.extend(data);
 
         let mut writer = Vec::new();
         frame.write_to(&mut writer, tag::Id3v23, false).unwrap();
@@ -337,12 +429,228 @@ mod tests {
 
         let mut bytes = Vec::new();
         bytes.extend(id.bytes());
-        bytes.extend(u32_to_bytes(unsynch::encode_u32(data.len() as u32)).into_iter());
+        bytes.extend(u32_to_bytes(unsynch::encode_u32(data.len() as u32)));
         bytes.extend([0x60, 0x00].iter().cloned());
-        bytes.extend(data.into_iter());
+        bytes.extend(data);
 
         let mut writer = Vec::new();
         frame.write_to(&mut writer, tag::Id3v24, false).unwrap();
         assert_eq!(writer, bytes);
     }
+
+    /// Writes `frame` out for the given version and reads it straight back, returning the
+    /// decoded frame.
+    fn round_trip(frame: &Frame, version: tag::Version) -> Frame {
+        let mut writer = Vec::new();
+        frame.write_to(&mut writer, version, false).unwrap();
+        let mut reader = io::Cursor::new(writer);
+        Frame::read_from(&mut reader, version, false).unwrap().unwrap().1
+    }
+
+    #[test]
+    fn test_round_trip_chapter_v3() {
+        let chapter = Chapter {
+            element_id: "chp1".to_owned(),
+            start_time: 0,
+            end_time: 5000,
+            start_byte_offset: CHAPTER_VALUE_NOT_SET,
+            end_byte_offset: CHAPTER_VALUE_NOT_SET,
+            frames: vec![Frame::with_content("TIT2", Content::Text("Chapter 1".to_owned()))],
+        };
+        let frame = Frame::with_content("CHAP", Content::Chapter(chapter.clone()));
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.content(), &Content::Chapter(chapter));
+    }
+
+    #[test]
+    fn test_write_over_depth_chapter_tree_fails_gracefully() {
+        // Build a CHAP tree nested one level deeper than the recursion guard allows, by hand
+        // rather than through `read_from`, so this exercises the write-side guard rather than
+        // the decode-side one.
+        let mut chapter = Chapter {
+            element_id: "innermost".to_owned(),
+            start_time: 0,
+            end_time: 0,
+            start_byte_offset: CHAPTER_VALUE_NOT_SET,
+            end_byte_offset: CHAPTER_VALUE_NOT_SET,
+            frames: Vec::new(),
+        };
+        for _ in 0..::stream::frame::MAX_CHAPTER_RECURSION_DEPTH {
+            let inner = Frame::with_content("CHAP", Content::Chapter(chapter));
+            chapter = Chapter {
+                element_id: "outer".to_owned(),
+                start_time: 0,
+                end_time: 0,
+                start_byte_offset: CHAPTER_VALUE_NOT_SET,
+                end_byte_offset: CHAPTER_VALUE_NOT_SET,
+                frames: vec![inner],
+            };
+        }
+        let frame = Frame::with_content("CHAP", Content::Chapter(chapter));
+
+        let mut writer = Vec::new();
+        assert!(frame.write_to(&mut writer, tag::Id3v23, false).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_table_of_contents_v4() {
+        let toc = TableOfContents {
+            element_id: "toc".to_owned(),
+            top_level: true,
+            ordered: true,
+            children: vec!["chp1".to_owned(), "chp2".to_owned()],
+            frames: vec![Frame::with_content("TIT2", Content::Text("Table of Contents".to_owned()))],
+        };
+        let frame = Frame::with_content("CTOC", Content::TableOfContents(toc.clone()));
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.content(), &Content::TableOfContents(toc));
+    }
+
+    #[test]
+    fn test_round_trip_synchronised_lyrics_v3() {
+        let lyrics = SynchronisedLyrics {
+            encoding: Encoding::UTF16,
+            lang: "eng".to_owned(),
+            timestamp_format: 2,
+            content_type: 1,
+            description: "lyrics".to_owned(),
+            content: vec![(0, "line one".to_owned()), (2500, "line two".to_owned())],
+        };
+        let frame = Frame::with_content("SYLT", Content::SynchronisedLyrics(lyrics.clone()));
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.content(), &Content::SynchronisedLyrics(lyrics));
+    }
+
+    #[test]
+    fn test_round_trip_synchronised_lyrics_v4() {
+        let lyrics = SynchronisedLyrics {
+            encoding: Encoding::UTF8,
+            lang: "eng".to_owned(),
+            timestamp_format: 1,
+            content_type: 0,
+            description: String::new(),
+            content: vec![(1000, "only line".to_owned())],
+        };
+        let frame = Frame::with_content("SYLT", Content::SynchronisedLyrics(lyrics.clone()));
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.content(), &Content::SynchronisedLyrics(lyrics));
+    }
+
+    #[test]
+    fn test_round_trip_popularimeter_v3() {
+        let popm = Popularimeter {
+            user: "user@example.com".to_owned(),
+            rating: 196,
+            counter: 42,
+        };
+        let frame = Frame::with_content("POPM", Content::Popularimeter(popm.clone()));
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.content(), &Content::Popularimeter(popm));
+    }
+
+    #[test]
+    fn test_round_trip_popularimeter_large_counter_v4() {
+        let popm = Popularimeter {
+            user: "user@example.com".to_owned(),
+            rating: 255,
+            counter: 0x1_0000_0000,
+        };
+        let frame = Frame::with_content("POPM", Content::Popularimeter(popm.clone()));
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.content(), &Content::Popularimeter(popm));
+    }
+
+    #[test]
+    fn test_round_trip_relative_volume_adjustment_v3() {
+        let mut adjustments = HashMap::new();
+        adjustments.insert(ChannelType::MasterVolume, VolumeAdjustment { gain_db: -3.5, peak: 12345 });
+        adjustments.insert(ChannelType::FrontLeft, VolumeAdjustment { gain_db: 2.0, peak: 0 });
+        let rva = RelativeVolumeAdjustment { identifier: "normalize".to_owned(), adjustments };
+        let frame = Frame::with_content("RVA2", Content::RelativeVolumeAdjustment(rva.clone()));
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.content(), &Content::RelativeVolumeAdjustment(rva));
+    }
+
+    #[test]
+    fn test_round_trip_relative_volume_adjustment_v4() {
+        let mut adjustments = HashMap::new();
+        adjustments.insert(ChannelType::Subwoofer, VolumeAdjustment { gain_db: 0.0, peak: 1 });
+        let rva = RelativeVolumeAdjustment { identifier: String::new(), adjustments };
+        let frame = Frame::with_content("RVA2", Content::RelativeVolumeAdjustment(rva.clone()));
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.content(), &Content::RelativeVolumeAdjustment(rva));
+    }
+
+    #[test]
+    fn test_round_trip_encapsulated_object_v3() {
+        let geob = EncapsulatedObject {
+            encoding: Encoding::UTF16,
+            mime_type: "application/octet-stream".to_owned(),
+            filename: "data.bin".to_owned(),
+            description: "attachment".to_owned(),
+            data: vec![0x00, 0xFF, 0x10, 0x20, 0x00],
+        };
+        let frame = Frame::with_content("GEOB", Content::EncapsulatedObject(geob.clone()));
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.content(), &Content::EncapsulatedObject(geob));
+    }
+
+    #[test]
+    fn test_round_trip_encapsulated_object_v4() {
+        let geob = EncapsulatedObject {
+            encoding: Encoding::UTF8,
+            mime_type: "text/plain".to_owned(),
+            filename: String::new(),
+            description: String::new(),
+            data: Vec::new(),
+        };
+        let frame = Frame::with_content("GEOB", Content::EncapsulatedObject(geob.clone()));
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.content(), &Content::EncapsulatedObject(geob));
+    }
+
+    #[test]
+    fn test_round_trip_group_identifier_and_encryption_method_v3() {
+        let mut frame = Frame::with_content("TIT2", Content::Text("title".to_owned()));
+        frame.set_group_identifier(7);
+        frame.set_encryption_method(3);
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.group_identifier(), Some(7));
+        assert_eq!(decoded.encryption_method(), Some(3));
+        assert_eq!(decoded.content(), frame.content());
+    }
+
+    #[test]
+    fn test_round_trip_group_identifier_and_encryption_method_v4() {
+        let mut frame = Frame::with_content("TIT2", Content::Text("title".to_owned()));
+        frame.set_group_identifier(42);
+        frame.set_encryption_method(9);
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.group_identifier(), Some(42));
+        assert_eq!(decoded.encryption_method(), Some(9));
+        assert_eq!(decoded.content(), frame.content());
+    }
+
+    #[test]
+    fn test_round_trip_decompressed_size_v3() {
+        let mut frame = Frame::with_content("TIT2", Content::Text("title".to_owned()));
+        frame.set_compression(true);
+        frame.set_data_length(12345);
+        let decoded = round_trip(&frame, tag::Id3v23);
+        assert_eq!(decoded.data_length(), Some(12345));
+        assert_eq!(decoded.content(), frame.content());
+    }
+
+    #[test]
+    fn test_round_trip_data_length_indicator_v4() {
+        let mut frame = Frame::with_content("TIT2", Content::Text("title".to_owned()));
+        frame.set_group_identifier(1);
+        frame.set_encryption_method(2);
+        frame.set_data_length(987654);
+        let decoded = round_trip(&frame, tag::Id3v24);
+        assert_eq!(decoded.group_identifier(), Some(1));
+        assert_eq!(decoded.encryption_method(), Some(2));
+        assert_eq!(decoded.data_length(), Some(987654));
+        assert_eq!(decoded.content(), frame.content());
+    }
 }
@@ -0,0 +1,306 @@
+use ::frame::content::default_encoding;
+use ::frame::{Content, Frame, Picture};
+use ::stream::encoding::Encoding;
+use ::tag::Version;
+use ::{Error, ErrorKind};
+
+/// A set of restrictions declared by an ID3v2.4 extended header.
+///
+/// Passed to `Frame::validate_restrictions` so that a writer can confirm a frame conforms
+/// before serializing a restricted tag.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub struct Restrictions {
+    /// Restricts which text encodings a text frame may use.
+    pub text_encoding: TextEncodingRestriction,
+    /// Restricts the maximum length of a text field.
+    pub text_field_size: TextFieldSizeRestriction,
+    /// Restricts which image formats an attached picture may use.
+    pub image_encoding: ImageEncodingRestriction,
+    /// Restricts the pixel dimensions of an attached picture.
+    pub image_size: ImageSizeRestriction,
+}
+
+/// Restricts which text encoding a text frame may declare.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum TextEncodingRestriction {
+    /// No restriction.
+    #[default]
+    None,
+    /// Only ISO-8859-1 or UTF-8 may be used.
+    Utf8OrLatin1,
+}
+
+/// Restricts the maximum length of a text field, in characters.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum TextFieldSizeRestriction {
+    /// No restriction.
+    #[default]
+    None,
+    /// At most 1024 characters.
+    Max1024,
+    /// At most 128 characters.
+    Max128,
+    /// At most 30 characters.
+    Max30,
+}
+
+impl TextFieldSizeRestriction {
+    fn limit(&self) -> Option<usize> {
+        match *self {
+            TextFieldSizeRestriction::None => None,
+            TextFieldSizeRestriction::Max1024 => Some(1024),
+            TextFieldSizeRestriction::Max128 => Some(128),
+            TextFieldSizeRestriction::Max30 => Some(30),
+        }
+    }
+}
+
+/// Restricts which image formats an attached picture may use.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ImageEncodingRestriction {
+    /// No restriction.
+    #[default]
+    None,
+    /// Only PNG or JPEG may be used.
+    PngOrJpegOnly,
+}
+
+/// Restricts the pixel dimensions of an attached picture.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ImageSizeRestriction {
+    /// No restriction.
+    #[default]
+    None,
+    /// No larger than 256x256 pixels.
+    Max256x256,
+    /// No larger than 64x64 pixels.
+    Max64x64,
+    /// Exactly 64x64 pixels.
+    Exactly64x64,
+}
+
+impl Frame {
+    /// Validates this frame's content against a set of ID3v2.4 tag restrictions, returning an
+    /// error describing the first restriction that is violated.
+    ///
+    /// Only `Content::Text`, `Content::ExtendedText` and `Content::Picture` are checked; any
+    /// other content always passes.
+    ///
+    /// `version` is the tag version the frame will actually be written as; it determines which
+    /// text encoding `Content::Text`/`Content::ExtendedText` will be written in, which is what a
+    /// `TextEncodingRestriction` restricts.
+    pub fn validate_restrictions(&self, restrictions: &Restrictions, version: Version) -> ::Result<()> {
+        match self.content {
+            Content::Text(ref text) => {
+                validate_text_encoding(restrictions.text_encoding, version)?;
+                validate_text_field_size(text, restrictions.text_field_size)
+            },
+            Content::ExtendedText(ref extended_text) => {
+                validate_text_encoding(restrictions.text_encoding, version)?;
+                validate_text_field_size(&extended_text.value, restrictions.text_field_size)
+            },
+            Content::Picture(ref picture) => validate_picture(picture, restrictions),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn validate_text_encoding(restriction: TextEncodingRestriction, version: Version) -> ::Result<()> {
+    if restriction == TextEncodingRestriction::Utf8OrLatin1 {
+        // `Content::Text`/`Content::ExtendedText` are always written in `default_encoding(version)`
+        // (see `frame::content::encode_text`), which is UTF-16 for ID3v2.2/ID3v2.3 - violating a
+        // `Utf8OrLatin1` restriction regardless of the string's actual content.
+        let encoding = default_encoding(version);
+        if encoding != Encoding::Latin1 && encoding != Encoding::UTF8 {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                "text frame would be written in an encoding other than ISO-8859-1/UTF-8, violating the tag's declared restriction"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_text_field_size(text: &str, restriction: TextFieldSizeRestriction) -> ::Result<()> {
+    if let Some(limit) = restriction.limit() {
+        if text.chars().count() > limit {
+            return Err(Error::new(ErrorKind::InvalidInput, "text field exceeds the tag's declared size restriction"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_picture(picture: &Picture, restrictions: &Restrictions) -> ::Result<()> {
+    match restrictions.image_encoding {
+        ImageEncodingRestriction::None => {},
+        ImageEncodingRestriction::PngOrJpegOnly => {
+            if picture.mime_type != "image/png" && picture.mime_type != "image/jpeg" {
+                return Err(Error::new(ErrorKind::InvalidInput, "picture encoding is not PNG or JPEG as required by the tag's restrictions"));
+            }
+        },
+    }
+    if restrictions.image_size != ImageSizeRestriction::None {
+        let (width, height) = image_dimensions(&picture.mime_type, &picture.data)?;
+        let within_restriction = match restrictions.image_size {
+            ImageSizeRestriction::None => true,
+            ImageSizeRestriction::Max256x256 => width <= 256 && height <= 256,
+            ImageSizeRestriction::Max64x64 => width <= 64 && height <= 64,
+            ImageSizeRestriction::Exactly64x64 => width == 64 && height == 64,
+        };
+        if !within_restriction {
+            return Err(Error::new(ErrorKind::InvalidInput, "picture dimensions violate the tag's declared size restriction"));
+        }
+    }
+    Ok(())
+}
+
+/// Determines the pixel dimensions of a picture, returning `(width, height)`.
+///
+/// Only PNG and JPEG are supported, matching the only formats a `PngOrJpegOnly` encoding
+/// restriction allows; any other MIME type can not be measured.
+fn image_dimensions(mime_type: &str, data: &[u8]) -> ::Result<(u32, u32)> {
+    match mime_type {
+        "image/png" => png_dimensions(data),
+        "image/jpeg" => jpeg_dimensions(data),
+        _ => Err(Error::new(ErrorKind::UnsupportedFeature, "checking the image size restriction is only supported for PNG and JPEG pictures")),
+    }
+}
+
+fn be_u32(data: &[u8]) -> u32 {
+    ((data[0] as u32) << 24) | ((data[1] as u32) << 16) | ((data[2] as u32) << 8) | (data[3] as u32)
+}
+
+/// Reads the width/height out of a PNG's leading IHDR chunk.
+fn png_dimensions(data: &[u8]) -> ::Result<(u32, u32)> {
+    const SIGNATURE: [u8; 8] = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    if data.len() < 24 || data[..8] != SIGNATURE {
+        return Err(Error::new(ErrorKind::Parsing, "not a valid PNG file"));
+    }
+    Ok((be_u32(&data[16..20]), be_u32(&data[20..24])))
+}
+
+/// Reads the width/height out of a JPEG's first start-of-frame (SOFn) marker segment.
+fn jpeg_dimensions(data: &[u8]) -> ::Result<(u32, u32)> {
+    if data.len() < 4 || data[0] != 0xFF || data[1] != 0xD8 {
+        return Err(Error::new(ErrorKind::Parsing, "not a valid JPEG file"));
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return Err(Error::new(ErrorKind::Parsing, "malformed JPEG marker"));
+        }
+        let marker = data[pos + 1];
+        if marker == 0xD8 || marker == 0xD9 || marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+
+        let segment_len = ((data[pos + 2] as usize) << 8) | (data[pos + 3] as usize);
+        let is_sof = (0xC0..=0xCF).contains(&marker) && marker != 0xC4 && marker != 0xC8 && marker != 0xCC;
+        if is_sof {
+            if pos + 9 > data.len() {
+                return Err(Error::new(ErrorKind::Parsing, "truncated JPEG start-of-frame segment"));
+            }
+            let height = ((data[pos + 5] as u32) << 8) | (data[pos + 6] as u32);
+            let width = ((data[pos + 7] as u32) << 8) | (data[pos + 8] as u32);
+            return Ok((width, height));
+        }
+        pos += 2 + segment_len;
+    }
+    Err(Error::new(ErrorKind::Parsing, "no start-of-frame marker found in JPEG file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_with_dimensions(width: u32, height: u32) -> Vec<u8> {
+        let mut data = vec![0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+        data.extend(&[0, 0, 0, 13]);
+        data.extend(b"IHDR");
+        data.extend(&width.to_be_bytes());
+        data.extend(&height.to_be_bytes());
+        data.extend(&[8, 6, 0, 0, 0]);
+        data.extend(&[0, 0, 0, 0]);
+        data
+    }
+
+    fn jpeg_with_dimensions(width: u32, height: u32) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xD8];
+        data.extend(&[0xFF, 0xC0, 0x00, 0x0B, 0x08]);
+        data.extend(&(height as u16).to_be_bytes());
+        data.extend(&(width as u16).to_be_bytes());
+        data.extend(&[0x01, 0x01, 0x11, 0x00]);
+        data.extend(&[0xFF, 0xD9]);
+        data
+    }
+
+    fn picture(mime_type: &str, data: Vec<u8>) -> Picture {
+        Picture {
+            mime_type: mime_type.to_owned(),
+            picture_type: ::frame::PictureType::CoverFront,
+            description: String::new(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_validate_text_encoding_restriction_no_restriction_always_passes() {
+        let frame = Frame::with_content("TIT2", Content::Text("x".repeat(2000)));
+        let restrictions = Restrictions { text_field_size: TextFieldSizeRestriction::None, ..Default::default() };
+        assert!(frame.validate_restrictions(&restrictions, Version::Id3v22).is_ok());
+    }
+
+    #[test]
+    fn test_validate_text_encoding_restriction_utf8_or_latin1() {
+        let frame = Frame::with_content("TIT2", Content::Text("title".to_owned()));
+        let restrictions = Restrictions { text_encoding: TextEncodingRestriction::Utf8OrLatin1, ..Default::default() };
+        // ID3v2.2/ID3v2.3 always write text frames as UTF-16, which violates the restriction...
+        assert!(frame.validate_restrictions(&restrictions, Version::Id3v22).is_err());
+        assert!(frame.validate_restrictions(&restrictions, Version::Id3v23).is_err());
+        // ...but ID3v2.4 writes UTF-8 by default, which satisfies it.
+        assert!(frame.validate_restrictions(&restrictions, Version::Id3v24).is_ok());
+    }
+
+    #[test]
+    fn test_validate_text_field_size_restriction() {
+        let short = Frame::with_content("TIT2", Content::Text("short".to_owned()));
+        let long = Frame::with_content("TIT2", Content::Text("x".repeat(40)));
+        let restrictions = Restrictions { text_field_size: TextFieldSizeRestriction::Max30, ..Default::default() };
+        assert!(short.validate_restrictions(&restrictions, Version::Id3v24).is_ok());
+        assert!(long.validate_restrictions(&restrictions, Version::Id3v24).is_err());
+    }
+
+    #[test]
+    fn test_validate_image_encoding_restriction() {
+        let restrictions = Restrictions { image_encoding: ImageEncodingRestriction::PngOrJpegOnly, ..Default::default() };
+        let png = Frame::with_content("APIC", Content::Picture(picture("image/png", png_with_dimensions(10, 10))));
+        let gif = Frame::with_content("APIC", Content::Picture(picture("image/gif", Vec::new())));
+        assert!(png.validate_restrictions(&restrictions, Version::Id3v24).is_ok());
+        assert!(gif.validate_restrictions(&restrictions, Version::Id3v24).is_err());
+    }
+
+    #[test]
+    fn test_validate_image_size_restriction_png() {
+        let restrictions = Restrictions { image_size: ImageSizeRestriction::Max64x64, ..Default::default() };
+        let small = Frame::with_content("APIC", Content::Picture(picture("image/png", png_with_dimensions(64, 64))));
+        let large = Frame::with_content("APIC", Content::Picture(picture("image/png", png_with_dimensions(128, 128))));
+        assert!(small.validate_restrictions(&restrictions, Version::Id3v24).is_ok());
+        assert!(large.validate_restrictions(&restrictions, Version::Id3v24).is_err());
+    }
+
+    #[test]
+    fn test_validate_image_size_restriction_jpeg() {
+        let restrictions = Restrictions { image_size: ImageSizeRestriction::Exactly64x64, ..Default::default() };
+        let exact = Frame::with_content("APIC", Content::Picture(picture("image/jpeg", jpeg_with_dimensions(64, 64))));
+        let wrong = Frame::with_content("APIC", Content::Picture(picture("image/jpeg", jpeg_with_dimensions(64, 32))));
+        assert!(exact.validate_restrictions(&restrictions, Version::Id3v24).is_ok());
+        assert!(wrong.validate_restrictions(&restrictions, Version::Id3v24).is_err());
+    }
+
+    #[test]
+    fn test_validate_image_size_restriction_unsupported_format() {
+        let restrictions = Restrictions { image_size: ImageSizeRestriction::Max64x64, ..Default::default() };
+        let bmp = Frame::with_content("APIC", Content::Picture(picture("image/bmp", Vec::new())));
+        assert!(bmp.validate_restrictions(&restrictions, Version::Id3v24).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use std::fmt;
+
+/// A timestamp as used by ID3v2.4 date/time frames (e.g. `TDRC`), which may be given with
+/// varying precision from just a year up to a full second.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Timestamp {
+    /// The year.
+    pub year: i32,
+    /// The month (1-12), if known.
+    pub month: Option<u8>,
+    /// The day of the month (1-31), if known.
+    pub day: Option<u8>,
+    /// The hour (0-23), if known.
+    pub hour: Option<u8>,
+    /// The minute (0-59), if known.
+    pub minute: Option<u8>,
+    /// The second (0-59), if known.
+    pub second: Option<u8>,
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+            if let Some(day) = self.day {
+                write!(f, "-{:02}", day)?;
+                if let Some(hour) = self.hour {
+                    write!(f, "T{:02}", hour)?;
+                    if let Some(minute) = self.minute {
+                        write!(f, ":{:02}", minute)?;
+                        if let Some(second) = self.second {
+                            write!(f, ":{:02}", second)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+//! A library to read and write ID3v2 tags.
+
+pub use error::{Error, ErrorKind, Result};
+pub use frame::Frame;
+pub use tag::Version;
+
+pub mod frame;
+mod error;
+mod stream;
+mod tag;
+mod util;
@@ -0,0 +1,36 @@
+use ::{Error, ErrorKind};
+
+/// The text encoding used for a string field within a frame, as declared by the leading
+/// encoding byte of most text-bearing frames.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Encoding {
+    /// ISO-8859-1
+    Latin1 = 0,
+    /// UTF-16 with a byte order mark
+    UTF16 = 1,
+    /// UTF-16 big endian without a byte order mark
+    UTF16BE = 2,
+    /// UTF-8
+    UTF8 = 3,
+}
+
+impl Encoding {
+    /// Parses an encoding byte as it appears on the wire.
+    pub fn from_u8(n: u8) -> ::Result<Encoding> {
+        match n {
+            0 => Ok(Encoding::Latin1),
+            1 => Ok(Encoding::UTF16),
+            2 => Ok(Encoding::UTF16BE),
+            3 => Ok(Encoding::UTF8),
+            _ => Err(Error::new(ErrorKind::Parsing, "invalid text encoding byte")),
+        }
+    }
+
+    /// The number of null bytes that terminate a string using this encoding.
+    pub fn delim_len(&self) -> usize {
+        match *self {
+            Encoding::Latin1 | Encoding::UTF8 => 1,
+            Encoding::UTF16 | Encoding::UTF16BE => 2,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Per-version ID3v2 frame header (de)serialization.
+//!
+//! Frame bodies themselves (the `Content` enum) are encoded/decoded in `::frame::content`; this
+//! module is only responsible for the id/size/flags header that wraps them and for dispatching
+//! on the tag version.
+
+use std::io::Read;
+
+use ::frame::flags::Flags;
+use ::frame::{Content, Frame};
+use ::tag::Version;
+use ::{Error, ErrorKind, Result};
+
+pub mod v2;
+pub mod v3;
+pub mod v4;
+
+/// The maximum nesting depth allowed when decoding `CHAP`/`CTOC` frames that embed further
+/// frames, guarding against maliciously deep recursion.
+pub const MAX_CHAPTER_RECURSION_DEPTH: u8 = 8;
+
+/// Attempts to read a single frame from `reader`, encoded for the given tag version.
+///
+/// Returns a tuple of the number of bytes consumed and the frame, or `None` if padding was
+/// encountered instead of a frame.
+pub fn decode<R: Read>(reader: &mut R, version: Version, unsynchronization: bool) -> Result<Option<(usize, Frame)>> {
+    decode_with_depth(reader, version, unsynchronization, 0)
+}
+
+pub(crate) fn decode_with_depth<R: Read>(reader: &mut R, version: Version, unsynchronization: bool, depth: u8) -> Result<Option<(usize, Frame)>> {
+    match version {
+        Version::Id3v22 => v2::read(reader, unsynchronization, depth),
+        Version::Id3v23 => v3::read(reader, unsynchronization, depth),
+        Version::Id3v24 => v4::read(reader, depth),
+    }
+}
+
+/// Decodes the content of a frame from its already extracted body bytes (already
+/// un-unsynchronized, if applicable).
+///
+/// This does not handle `CHAP`/`CTOC`, whose embedded sub-frames require the tag version and
+/// unsynchronization state; those are decoded through `decode_body` instead.
+pub fn decode_content<R: Read>(reader: R, id: &str, flags: Flags) -> Result<Content> {
+    ::frame::content::decode(reader, id, flags)
+}
+
+/// Decodes a frame body (given its 4-character, ID3v2.3-style id) into `Content`, special-casing
+/// `CHAP`/`CTOC` which need the version and recursion depth to decode their embedded sub-frames.
+pub(crate) fn decode_body(id: &str, body: Vec<u8>, version: Version, unsynchronization: bool, flags: Flags, depth: u8) -> Result<Content> {
+    match id {
+        "CHAP" => ::frame::content::decode_chapter(&body, version, unsynchronization, depth),
+        "CTOC" => ::frame::content::decode_table_of_contents(&body, version, unsynchronization, depth),
+        _ => {
+            let body = if unsynchronization { ::stream::unsynch::decode_vec(&body) } else { body };
+            decode_content(&body[..], id, flags)
+        },
+    }
+}
+
+/// Decodes a buffer of concatenated, fully-framed sub-frames (as embedded in a `CHAP`/`CTOC`
+/// frame), stopping at the end of the buffer or at the first padding frame.
+pub(crate) fn decode_embedded_frames(data: &[u8], version: Version, unsynchronization: bool, depth: u8) -> Result<Vec<Frame>> {
+    let mut frames = Vec::new();
+    if depth >= MAX_CHAPTER_RECURSION_DEPTH {
+        return Ok(frames);
+    }
+    let mut cursor = ::std::io::Cursor::new(data);
+    while (cursor.position() as usize) < data.len() {
+        match decode_with_depth(&mut cursor, version, unsynchronization, depth + 1)? {
+            Some((_, frame)) => frames.push(frame),
+            None => break,
+        }
+    }
+    Ok(frames)
+}
+
+/// Encodes a frame's content into its raw body bytes, applying unsynchronization where
+/// applicable.
+///
+/// `CHAP`/`CTOC` bodies are not themselves re-unsynchronized here because they are mostly made up
+/// of already-unsynchronized, fully-serialized embedded frames; double-encoding would corrupt
+/// them.
+pub(crate) fn encode_body(frame: &Frame, version: Version, unsynchronization: bool, depth: u8) -> Result<Vec<u8>> {
+    let raw = ::frame::content::encode(frame.content(), version, unsynchronization, depth)?;
+    match *frame.content() {
+        Content::Chapter(_) | Content::TableOfContents(_) => Ok(raw),
+        _ => Ok(if unsynchronization { ::stream::unsynch::encode_vec(&raw) } else { raw }),
+    }
+}
+
+pub(crate) fn be_u32(data: &[u8]) -> u32 {
+    ((data[0] as u32) << 24) | ((data[1] as u32) << 16) | ((data[2] as u32) << 8) | (data[3] as u32)
+}
+
+pub(crate) fn be_u32_bytes(n: u32) -> [u8; 4] {
+    [(n >> 24) as u8, (n >> 16) as u8, (n >> 8) as u8, n as u8]
+}
+
+pub(crate) fn str_id(bytes: &[u8]) -> Result<String> {
+    ::std::str::from_utf8(bytes)
+        .map(|s| s.to_owned())
+        .map_err(|_| Error::new(ErrorKind::Parsing, "frame ID is not valid UTF-8"))
+}
+
+/// Reads exactly `buf.len()` bytes unless the reader is already at EOF, in which case `Ok(0)` is
+/// returned so callers can distinguish "no more frames" from a truncated header.
+pub(crate) fn read_header_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match reader.read(&mut buf[total..])? {
+            0 => break,
+            n => total += n,
+        }
+    }
+    if total != 0 && total != buf.len() {
+        return Err(Error::new(ErrorKind::Parsing, "unexpected eof while reading a frame header"));
+    }
+    Ok(total)
+}
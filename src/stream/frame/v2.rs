@@ -0,0 +1,47 @@
+//! ID3v2.2 frame header (de)serialization.
+//!
+//! ID3v2.2 frames have a 3-character ID, a 3-byte big-endian size and no frame flags at all.
+
+use std::io::{Read, Write};
+
+use ::frame::flags::Flags;
+use ::frame::Frame;
+use ::tag::Version;
+use ::{Error, ErrorKind, Result};
+
+use super::{be_u32_bytes, decode_body, encode_body, read_header_or_eof, str_id};
+
+/// Attempts to read a single ID3v2.2 frame from `reader`.
+pub fn read<R: Read>(reader: &mut R, unsynchronization: bool, depth: u8) -> Result<Option<(usize, Frame)>> {
+    let mut header = [0u8; 6];
+    if read_header_or_eof(reader, &mut header)? == 0 || header[0] == 0 {
+        return Ok(None);
+    }
+
+    let id = str_id(&header[0..3])?;
+    let size = ((header[3] as usize) << 16) | ((header[4] as usize) << 8) | (header[5] as usize);
+
+    let mut body = vec![0u8; size];
+    reader.read_exact(&mut body)?;
+
+    let id_v3 = ::util::convert_id_2_to_3(&id)
+        .ok_or_else(|| Error::new(ErrorKind::Parsing, format!("unknown ID3v2.2 frame ID '{}'", id)))?;
+
+    let content = decode_body(id_v3, body, Version::Id3v22, unsynchronization, Flags::new(), depth)?;
+    let frame = Frame::with_content(id_v3, content);
+    Ok(Some((6 + size, frame)))
+}
+
+/// Writes `frame` to `writer` in ID3v2.2 format.
+pub fn write<W: Write + ?Sized>(writer: &mut W, frame: &Frame, unsynchronization: bool, depth: u8) -> Result<u32> {
+    let id = frame.id_for_version(Version::Id3v22)
+        .ok_or_else(|| Error::new(ErrorKind::UnsupportedFeature, "this frame has no ID3v2.2 equivalent ID"))?;
+
+    let body = encode_body(frame, Version::Id3v22, unsynchronization, depth)?;
+    let size = body.len() as u32;
+
+    writer.write_all(id.as_bytes())?;
+    writer.write_all(&be_u32_bytes(size)[1..])?;
+    writer.write_all(&body)?;
+    Ok(6 + body.len() as u32)
+}
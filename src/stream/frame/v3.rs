@@ -0,0 +1,96 @@
+//! ID3v2.3 frame header (de)serialization.
+//!
+//! ID3v2.3 frames have a 4-character ID, a 4-byte big-endian (non-synchsafe) size and two flag
+//! bytes.
+
+use std::io::{Read, Write};
+
+use ::frame::flags::Flags;
+use ::frame::Frame;
+use ::tag::Version;
+use ::{Error, ErrorKind, Result};
+
+use super::{be_u32, be_u32_bytes, decode_body, encode_body, read_header_or_eof, str_id};
+
+/// Attempts to read a single ID3v2.3 frame from `reader`.
+pub fn read<R: Read>(reader: &mut R, unsynchronization: bool, depth: u8) -> Result<Option<(usize, Frame)>> {
+    let mut header = [0u8; 10];
+    if read_header_or_eof(reader, &mut header)? == 0 || header[0] == 0 {
+        return Ok(None);
+    }
+
+    let id = str_id(&header[0..4])?;
+    let size = be_u32(&header[4..8]) as usize;
+    let flags = Flags::from_bytes([header[8], header[9]], 3);
+
+    let mut body = vec![0u8; size];
+    reader.read_exact(&mut body)?;
+
+    // Order matches the ID3v2.3 spec's extended frame header: the decompressed size (if the
+    // frame is compressed) comes first, then the encryption method byte (if present), then the
+    // group identifier byte (if present).
+    let mut pos = 0;
+    let data_length = if flags.compression {
+        if pos + 4 > body.len() {
+            return Err(Error::new(ErrorKind::Parsing, "frame is missing its decompressed size field"));
+        }
+        let value = be_u32(&body[pos..pos + 4]);
+        pos += 4;
+        Some(value)
+    } else {
+        None
+    };
+    let encryption_method = if flags.encryption {
+        let byte = *body.get(pos)
+            .ok_or_else(|| Error::new(ErrorKind::Parsing, "frame is missing its encryption method byte"))?;
+        pos += 1;
+        Some(byte)
+    } else {
+        None
+    };
+    let group_identifier = if flags.grouping_identity {
+        let byte = *body.get(pos)
+            .ok_or_else(|| Error::new(ErrorKind::Parsing, "frame is missing its group identifier byte"))?;
+        pos += 1;
+        Some(byte)
+    } else {
+        None
+    };
+
+    let content = decode_body(&id, body[pos..].to_vec(), Version::Id3v23, unsynchronization, flags, depth)?;
+    let mut frame = Frame::with_content(&id, content);
+    frame.flags = flags;
+    frame.encryption_method = encryption_method;
+    frame.group_identifier = group_identifier;
+    frame.data_length = data_length;
+    Ok(Some((10 + size, frame)))
+}
+
+/// Writes `frame` to `writer` in ID3v2.3 format.
+pub fn write<W: Write + ?Sized>(writer: &mut W, frame: &Frame, unsynchronization: bool, depth: u8) -> Result<u32> {
+    let id = frame.id_for_version(Version::Id3v23)
+        .expect("ID3v2.3/ID3v2.4 ids are always available");
+
+    let mut body = Vec::new();
+    if frame.flags.compression {
+        let data_length = frame.data_length
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "the compression flag is set but no decompressed size was given"))?;
+        body.extend(&be_u32_bytes(data_length));
+    }
+    if frame.flags.encryption {
+        body.push(frame.encryption_method
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "the encryption flag is set but no encryption method byte was given"))?);
+    }
+    if frame.flags.grouping_identity {
+        body.push(frame.group_identifier
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "the grouping_identity flag is set but no group identifier byte was given"))?);
+    }
+    body.extend(encode_body(frame, Version::Id3v23, unsynchronization, depth)?);
+    let size = body.len() as u32;
+
+    writer.write_all(id.as_bytes())?;
+    writer.write_all(&be_u32_bytes(size))?;
+    writer.write_all(&frame.flags.to_bytes(3))?;
+    writer.write_all(&body)?;
+    Ok(10 + body.len() as u32)
+}
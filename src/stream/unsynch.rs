@@ -0,0 +1,42 @@
+//! Synchsafe integer and unsynchronisation helpers used by the frame/tag readers and writers.
+
+/// Encodes a 28-bit integer as a 32-bit synchsafe integer (used for ID3v2.4 frame sizes).
+pub fn encode_u32(n: u32) -> u32 {
+    let mut x = n & 0x7F | (n & 0xFFFFFF80) << 1;
+    x = x & 0x7FFF | (x & 0xFFFF8000) << 1;
+    x = x & 0x7FFFFF | (x & 0xFF800000) << 1;
+    x
+}
+
+/// Decodes a 32-bit synchsafe integer back into a regular integer.
+pub fn decode_u32(n: u32) -> u32 {
+    (n & 0xFF) | (n & 0xFF00) >> 1 | (n & 0xFF0000) >> 2 | (n & 0xFF000000) >> 3
+}
+
+/// Applies unsynchronisation to a byte stream, inserting a `0x00` after every `0xFF` byte that
+/// is followed by `0x00`, by a byte with its top three bits set, or by the end of the data, so
+/// that no byte sequence in the output can be mistaken for an MPEG frame sync.
+pub fn encode_vec(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for (i, &byte) in data.iter().enumerate() {
+        out.push(byte);
+        if byte == 0xFF && data.get(i + 1).is_none_or(|&next| next == 0x00 || next & 0xE0 == 0xE0) {
+            out.push(0x00);
+        }
+    }
+    out
+}
+
+/// Reverses `encode_vec`, removing the `0x00` byte inserted after every `0xFF` byte.
+pub fn decode_vec(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        out.push(data[i]);
+        if data[i] == 0xFF && data.get(i + 1) == Some(&0x00) {
+            i += 1;
+        }
+        i += 1;
+    }
+    out
+}
@@ -0,0 +1,12 @@
+pub use self::Version::{Id3v22, Id3v23, Id3v24};
+
+/// Identifies the version of an ID3 tag or frame.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Version {
+    /// ID3v2.2
+    Id3v22,
+    /// ID3v2.3
+    Id3v23,
+    /// ID3v2.4
+    Id3v24,
+}
@@ -0,0 +1,59 @@
+//! Small helpers shared across the tag/frame readers and writers.
+
+/// Converts a three character ID3v2.2 frame ID to the equivalent four character ID3v2.3/ID3v2.4
+/// ID, if a mapping is known.
+pub fn convert_id_2_to_3(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "BUF" => "RBUF", "CNT" => "PCNT", "COM" => "COMM", "CRA" => "AENC", "ETC" => "ETCO",
+        "GEO" => "GEOB", "IPL" => "IPLS", "MCI" => "MCDI", "MLL" => "MLLT", "PIC" => "APIC",
+        "POP" => "POPM", "REV" => "RVRB", "RVA" => "RVAD", "SLT" => "SYLT", "STC" => "SYTC",
+        "TAL" => "TALB", "TBP" => "TBPM", "TCM" => "TCOM", "TCO" => "TCON", "TCR" => "TCOP",
+        "TDA" => "TDAT", "TDY" => "TDLY", "TEN" => "TENC", "TFT" => "TFLT", "TIM" => "TIME",
+        "TKE" => "TKEY", "TLA" => "TLAN", "TLE" => "TLEN", "TMT" => "TMED", "TOA" => "TOPE",
+        "TOF" => "TOFN", "TOL" => "TOLY", "TOR" => "TORY", "TOT" => "TOAL", "TP1" => "TPE1",
+        "TP2" => "TPE2", "TP3" => "TPE3", "TP4" => "TPE4", "TPA" => "TPOS", "TPB" => "TPUB",
+        "TRC" => "TSRC", "TRD" => "TRDA", "TRK" => "TRCK", "TSI" => "TSIZ", "TSS" => "TSSE",
+        "TT1" => "TIT1", "TT2" => "TIT2", "TT3" => "TIT3", "TXT" => "TEXT", "TXX" => "TXXX",
+        "TYE" => "TYER", "UFI" => "UFID", "ULT" => "USLT", "WAF" => "WOAF", "WAR" => "WOAR",
+        "WAS" => "WOAS", "WCM" => "WCOM", "WCP" => "WCOP", "WPB" => "WPUB", "WXX" => "WXXX",
+        _ => return None,
+    })
+}
+
+/// Converts a four character ID3v2.3/ID3v2.4 frame ID to the equivalent three character
+/// ID3v2.2 ID, if a mapping is known.
+pub fn convert_id_3_to_2(id: &str) -> Option<&'static str> {
+    Some(match id {
+        "RBUF" => "BUF", "PCNT" => "CNT", "COMM" => "COM", "AENC" => "CRA", "ETCO" => "ETC",
+        "GEOB" => "GEO", "IPLS" => "IPL", "MCDI" => "MCI", "MLLT" => "MLL", "APIC" => "PIC",
+        "POPM" => "POP", "RVRB" => "REV", "RVAD" => "RVA", "SYLT" => "SLT", "SYTC" => "STC",
+        "TALB" => "TAL", "TBPM" => "TBP", "TCOM" => "TCM", "TCON" => "TCO", "TCOP" => "TCR",
+        "TDAT" => "TDA", "TDLY" => "TDY", "TENC" => "TEN", "TFLT" => "TFT", "TIME" => "TIM",
+        "TKEY" => "TKE", "TLAN" => "TLA", "TLEN" => "TLE", "TMED" => "TMT", "TOPE" => "TOA",
+        "TOFN" => "TOF", "TOLY" => "TOL", "TORY" => "TOR", "TOAL" => "TOT", "TPE1" => "TP1",
+        "TPE2" => "TP2", "TPE3" => "TP3", "TPE4" => "TP4", "TPOS" => "TPA", "TPUB" => "TPB",
+        "TSRC" => "TRC", "TRDA" => "TRD", "TRCK" => "TRK", "TSIZ" => "TSI", "TSSE" => "TSS",
+        "TIT1" => "TT1", "TIT2" => "TT2", "TIT3" => "TT3", "TEXT" => "TXT", "TXXX" => "TXX",
+        "TYER" => "TYE", "UFID" => "UFI", "USLT" => "ULT", "WOAF" => "WAF", "WOAR" => "WAR",
+        "WOAS" => "WAS", "WCOM" => "WCM", "WCOP" => "WCP", "WPUB" => "WPB", "WXXX" => "WXX",
+        _ => return None,
+    })
+}
+
+/// Encodes a string as UTF-16 with a little endian byte order mark, followed by a null
+/// terminator.
+pub fn string_to_utf16(text: &str) -> Vec<u8> {
+    let mut out = vec![0xFF, 0xFE];
+    out.extend(string_to_utf16le(text));
+    out
+}
+
+/// Encodes a string as UTF-16LE (no byte order mark), followed by a null terminator.
+pub fn string_to_utf16le(text: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    for unit in text.encode_utf16() {
+        out.extend(&unit.to_le_bytes());
+    }
+    out.extend(&[0x00, 0x00]);
+    out
+}